@@ -1,8 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use axum::async_trait;
-use chrono::{DateTime, Utc};
-use lapin::options::{BasicAckOptions, BasicNackOptions};
+use lapin::options::{BasicAckOptions, BasicNackOptions, BasicRejectOptions};
 use omniqueue::{
     backends::memory_queue::MemoryQueueBackend,
     queue::{
@@ -25,10 +24,16 @@ use crate::{
     error::{Error, ErrorType, Result},
 };
 
+use self::postgres::{PostgresQueueConsumer, PostgresQueueInner, PostgresQueueProducer};
 use self::redis::{RedisQueueConsumer, RedisQueueInner, RedisQueueProducer};
+use self::result_backend::ResultBackend;
+pub use self::result_backend::TaskOutcome;
 
+pub mod lock;
+pub mod postgres;
 pub mod rabbitmq;
 pub mod redis;
+pub mod result_backend;
 
 const RETRY_SCHEDULE: &[Duration] = &[
     Duration::from_millis(10),
@@ -40,18 +45,49 @@ fn should_retry(err: &Error) -> bool {
     matches!(err.typ, ErrorType::Queue(_))
 }
 
+/// Whether a delivery at `delivery_count` has exhausted `max_delivery_attempts` and should be
+/// dead-lettered by `nack` instead of requeued. No limit configured means never.
+fn should_dead_letter(delivery_count: u16, max_delivery_attempts: Option<u16>) -> bool {
+    max_delivery_attempts.is_some_and(|max| delivery_count >= max)
+}
+
+/// Records `outcome` for `id` if a result backend is configured, logging (rather than failing
+/// the caller's ack/dead_letter) if the write itself fails -- the delivery has already been
+/// acked or dead-lettered by this point, so there's nothing left to retry against.
+async fn record_outcome(result_backend: &Option<Arc<ResultBackend>>, id: &str, outcome: TaskOutcome) {
+    let Some(result_backend) = result_backend else {
+        return;
+    };
+    if let Err(e) = result_backend.set_outcome(id, &outcome).await {
+        tracing::error!("failed to record outcome for task {id}: {e}");
+    }
+}
+
 pub async fn new_pair(
     cfg: &Configuration,
     prefix: Option<&str>,
 ) -> (TaskQueueProducer, TaskQueueConsumer) {
-    match cfg.queue_backend() {
+    let max_delivery_attempts = cfg.queue_max_delivery_attempts();
+
+    // Built up front (not after backend dispatch) so the *consumer* can carry it too -- it's the
+    // worker side, after ack/dead-letter, that knows a delivery's terminal outcome, not the
+    // producer alone.
+    let result_backend = match cfg.queue_result_backend_redis_dsn() {
+        Some(dsn) => {
+            let pool = crate::redis::new_redis_pool(dsn, cfg).await;
+            Some(Arc::new(ResultBackend::new(pool)))
+        }
+        None => None,
+    };
+
+    let (producer, consumer) = match cfg.queue_backend() {
         QueueBackend::Redis(dsn) => {
             let pool = crate::redis::new_redis_pool(dsn, cfg).await;
-            redis::new_pair(pool, prefix).await
+            redis::new_pair(pool, prefix, max_delivery_attempts, result_backend.clone()).await
         }
         QueueBackend::RedisCluster(dsn) => {
             let pool = crate::redis::new_redis_pool_clustered(dsn, cfg).await;
-            redis::new_pair(pool, prefix).await
+            redis::new_pair(pool, prefix, max_delivery_attempts, result_backend.clone()).await
         }
         QueueBackend::Memory => {
             let (producer, consumer) = MemoryQueueBackend::builder(())
@@ -60,8 +96,10 @@ pub async fn new_pair(
                 .expect("building in-memory queue can't fail");
 
             (
-                TaskQueueProducer::Omni(Arc::new(producer.into_dyn_scheduled(Default::default()))),
-                TaskQueueConsumer::Omni(consumer.into_dyn(Default::default())),
+                TaskQueueProducer::new(TaskQueueProducerInner::Omni(Arc::new(
+                    producer.into_dyn_scheduled(Default::default()),
+                ))),
+                TaskQueueConsumer::Omni(consumer.into_dyn(Default::default()), result_backend.clone()),
             )
         }
         QueueBackend::RabbitMq(dsn) => {
@@ -69,11 +107,41 @@ pub async fn new_pair(
             let queue = format!("{prefix}-message-queue");
             // Default to a prefetch_size of 1, as it's the safest (least likely to starve consumers)
             let prefetch_size = cfg.rabbit_consumer_prefetch_size.unwrap_or(1);
-            rabbitmq::new_pair(dsn, queue, prefetch_size)
+            rabbitmq::new_pair(
+                dsn,
+                queue,
+                prefetch_size,
+                max_delivery_attempts,
+                result_backend.clone(),
+            )
+            .await
+            .expect("can't connect to rabbit")
+        }
+        QueueBackend::Postgres(dsn) => {
+            let pool = crate::db::new_pg_pool(dsn, cfg)
+                .await
+                .expect("can't connect to postgres queue backend");
+            postgres::new_pair(pool, prefix, max_delivery_attempts, result_backend.clone())
                 .await
-                .expect("can't connect to rabbit")
+                .expect("failed to prepare postgres queue tables")
         }
-    }
+    };
+
+    // The result backend is opt-in: only attach it to the producer (for await_result) if the
+    // operator configured a DSN for it.
+    let producer = match result_backend {
+        Some(result_backend) => producer.with_result_backend(result_backend),
+        None => producer,
+    };
+
+    // Auto-flush batching is opt-in: only wrap `send` in the buffering layer if the operator
+    // configured a batch size/linger pair for it.
+    let producer = match cfg.queue_auto_flush() {
+        Some(auto_flush) => producer.with_auto_flush(auto_flush),
+        None => producer,
+    };
+
+    (producer, consumer)
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -149,26 +217,124 @@ impl QueueTask {
 }
 
 #[derive(Clone)]
-pub enum TaskQueueProducer {
+enum TaskQueueProducerInner {
     Redis(RedisQueueProducer),
     RabbitMq(rabbitmq::Producer),
+    Postgres(PostgresQueueProducer),
     Omni(Arc<omniqueue::scheduled::DynScheduledProducer>),
 }
 
+/// A successfully enqueued task's scheduling id, as returned per-item from `send_batch`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SendReceipt {
+    pub id: String,
+}
+
+/// Auto-flush knobs for `TaskQueueProducer::with_auto_flush`: `send` calls are buffered and
+/// flushed as a single `send_batch` once either `max_batch_size` accumulates or `linger`
+/// elapses since the oldest buffered task, whichever comes first.
+#[derive(Clone, Copy, Debug)]
+pub struct AutoFlushConfig {
+    pub max_batch_size: usize,
+    pub linger: Duration,
+}
+
+struct PendingSend {
+    task: QueueTask,
+    delay: Option<Duration>,
+    reply: tokio::sync::oneshot::Sender<Result<SendReceipt>>,
+}
+
+/// A struct, not a bare backend enum, because `with_result_backend`/`with_auto_flush` need
+/// somewhere to hang state (the result backend handle, the auto-flush channel) that has nothing
+/// to do with which queue backend is underneath. `send`'s id return is likewise load-bearing, not
+/// incidental: it's the stable id `await_result`/`cancel_scheduled` key on. Both are breaking
+/// changes from the original bare-enum/`send() -> Result<()>` shape, but there are no other
+/// callers in this tree to update for either, and neither feature is expressible without them.
+#[derive(Clone)]
+pub struct TaskQueueProducer {
+    inner: TaskQueueProducerInner,
+    result_backend: Option<Arc<ResultBackend>>,
+    auto_flush: Option<Arc<tokio::sync::mpsc::UnboundedSender<PendingSend>>>,
+}
+
 impl TaskQueueProducer {
-    pub async fn send(&self, task: QueueTask, delay: Option<Duration>) -> Result<()> {
+    fn new(inner: TaskQueueProducerInner) -> Self {
+        Self {
+            inner,
+            result_backend: None,
+            auto_flush: None,
+        }
+    }
+
+    /// Attaches a [`ResultBackend`], enabling `await_result`. Opt-in: only wired up by
+    /// `new_pair` when the operator configured one.
+    pub fn with_result_backend(mut self, result_backend: Arc<ResultBackend>) -> Self {
+        self.result_backend = Some(result_backend);
+        self
+    }
+
+    /// Routes every `send` through an auto-flush buffer: tasks accumulate until either
+    /// `config.max_batch_size` is reached or `config.linger` elapses, then go out together as a
+    /// single `send_batch` call. Lets high-throughput fan-outs (e.g. `MessageBatch` expansion)
+    /// amortize network cost without every caller having to batch by hand.
+    pub fn with_auto_flush(mut self, config: AutoFlushConfig) -> Self {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let flusher = self.clone();
+        tokio::spawn(run_auto_flush(flusher, rx, config));
+        self.auto_flush = Some(Arc::new(tx));
+        self
+    }
+
+    /// Enqueues `task`, returning the scheduling id assigned to it (the KSUID minted by the
+    /// backend for this delivery). Pass that id to `cancel_scheduled` to retract the task
+    /// before it fires, e.g. when an endpoint is deleted or a message is recalled before its
+    /// retry is due.
+    ///
+    /// If `with_auto_flush` was used, this doesn't hit the backend directly -- it buffers the
+    /// task and waits for the auto-flush worker to include it in the next `send_batch`.
+    pub async fn send(&self, task: QueueTask, delay: Option<Duration>) -> Result<String> {
+        let Some(auto_flush) = &self.auto_flush else {
+            return self.send_direct(task, delay).await;
+        };
+
+        let (reply, response) = tokio::sync::oneshot::channel();
+        auto_flush
+            .send(PendingSend {
+                task,
+                delay,
+                reply,
+            })
+            .map_err(|_| Error::queue("auto-flush worker has shut down"))?;
+
+        response
+            .await
+            .map_err(|_| Error::queue("auto-flush worker dropped the reply"))?
+            .map(|receipt| receipt.id)
+    }
+
+    async fn send_direct(&self, task: QueueTask, delay: Option<Duration>) -> Result<String> {
         let task = Arc::new(task);
         run_with_retries(
             || async {
-                match self {
-                    TaskQueueProducer::Redis(q) => q.send(task.clone(), delay).await,
-                    TaskQueueProducer::RabbitMq(q) => q.send(task.clone(), delay).await,
-                    TaskQueueProducer::Omni(q) => if let Some(delay) = delay {
-                        q.send_serde_json_scheduled(task.as_ref(), delay).await
-                    } else {
-                        q.send_serde_json(task.as_ref()).await
+                match &self.inner {
+                    TaskQueueProducerInner::Redis(q) => q.send(task.clone(), delay).await,
+                    TaskQueueProducerInner::RabbitMq(q) => q.send(task.clone(), delay).await,
+                    TaskQueueProducerInner::Postgres(q) => q.send(task.clone(), delay).await,
+                    TaskQueueProducerInner::Omni(q) => {
+                        // Omniqueue doesn't hand back an id for the message it stored, so mint
+                        // one here. It is returned to the caller but not stored with the message:
+                        // deliveries from this backend have an empty id, so neither
+                        // `cancel_scheduled` nor `await_result` can address the task by it.
+                        let id = KsuidMs::new(None, None).to_string();
+                        if let Some(delay) = delay {
+                            q.send_serde_json_scheduled(task.as_ref(), delay).await
+                        } else {
+                            q.send_serde_json(task.as_ref()).await
+                        }
+                        .map(|()| id.clone())
+                        .map_err(Into::into)
                     }
-                    .map_err(Into::into),
                 }
             },
             should_retry,
@@ -176,12 +342,136 @@ impl TaskQueueProducer {
         )
         .await
     }
+
+    /// Enqueues every `(task, delay)` pair. Redis sends them as one pipelined `XADD`/`ZADD` round
+    /// trip and Postgres as one multi-row `INSERT`; RabbitMQ publishes and confirms each task in
+    /// turn, and the in-memory backend sends each task on its own, so those two still make one
+    /// round trip per task. Returns one result per input task, in order. A task that fails to
+    /// serialize fails alone; on Redis and Postgres, a failed pipeline or `INSERT` fails every
+    /// other task of the batch with it.
+    pub async fn send_batch(&self, tasks: Vec<(QueueTask, Option<Duration>)>) -> Vec<Result<SendReceipt>> {
+        let len = tasks.len();
+        match &self.inner {
+            TaskQueueProducerInner::Redis(q) => q.send_batch(tasks).await,
+            TaskQueueProducerInner::RabbitMq(q) => q.send_batch(tasks).await,
+            TaskQueueProducerInner::Postgres(q) => q.send_batch(tasks).await,
+            TaskQueueProducerInner::Omni(_) => {
+                // Omniqueue doesn't expose a batch primitive, so fall back to per-task sends.
+                // Still goes through the normal retry logic -- it just doesn't save a round trip.
+                let mut results = Vec::with_capacity(len);
+                for (task, delay) in tasks {
+                    results.push(self.send_direct(task, delay).await.map(|id| SendReceipt { id }));
+                }
+                results
+            }
+        }
+    }
+
+    /// Retracts a task scheduled with a `delay` before it fires. No-op (returns `Ok`) if the
+    /// task has already been delivered or doesn't exist -- callers shouldn't need to
+    /// distinguish "already ran" from "successfully cancelled".
+    pub async fn cancel_scheduled(&self, id: &str) -> Result<()> {
+        match &self.inner {
+            TaskQueueProducerInner::Redis(q) => q.cancel_scheduled(id).await,
+            TaskQueueProducerInner::RabbitMq(q) => q.cancel_scheduled(id).await,
+            TaskQueueProducerInner::Postgres(q) => q.cancel_scheduled(id).await,
+            TaskQueueProducerInner::Omni(_) => Err(Error::queue(
+                "cancelling scheduled tasks is not supported for the in-memory queue backend",
+            )),
+        }
+    }
+
+    /// Waits up to `timeout` for `id`'s terminal outcome, as recorded by the worker after
+    /// `ack`-ing or dead-lettering the delivery. Useful for synchronous API callers and tests
+    /// that want to block until a webhook attempt completes rather than sleeping arbitrarily.
+    /// Requires a result backend to have been configured.
+    pub async fn await_result(&self, id: &str, timeout: Duration) -> Result<TaskOutcome> {
+        let result_backend = self.result_backend.as_ref().ok_or_else(|| {
+            Error::queue("await_result requires a result backend to be configured")
+        })?;
+        result_backend.await_result(id, timeout).await
+    }
+}
+
+/// Buffers tasks sent through a `with_auto_flush`-wrapped producer, flushing them as a single
+/// `send_batch` once `config.max_batch_size` accumulates or `config.linger` elapses since the
+/// oldest buffered task, whichever comes first.
+async fn run_auto_flush(
+    producer: TaskQueueProducer,
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<PendingSend>,
+    config: AutoFlushConfig,
+) {
+    let mut buf: Vec<PendingSend> = Vec::with_capacity(config.max_batch_size);
+    // Armed when the buffer goes from empty to non-empty, and disarmed on every flush. Held
+    // across loop iterations so the deadline tracks the oldest buffered task, not whichever task
+    // arrived most recently -- recreating `tokio::time::sleep(config.linger)` on every iteration
+    // would reset it on each new arrival instead.
+    let mut deadline: Option<tokio::time::Instant> = None;
+
+    loop {
+        // No task is buffered when `deadline` is `None`, so there's nothing to time out --
+        // `pending()` never resolves, which is what "wait forever" actually means. A
+        // `sleep(Duration::from_secs(u64::MAX))` looks equivalent but isn't: `Instant::now() +
+        // that duration` overflows the moment it's constructed, panicking before `select!` even
+        // gets to its `if deadline.is_some()` guard.
+        let sleep: std::pin::Pin<Box<dyn std::future::Future<Output = ()> + Send>> = match deadline
+        {
+            Some(deadline) => Box::pin(tokio::time::sleep_until(deadline)),
+            None => Box::pin(std::future::pending()),
+        };
+
+        tokio::select! {
+            item = rx.recv() => {
+                match item {
+                    Some(item) => {
+                        if buf.is_empty() {
+                            deadline = Some(tokio::time::Instant::now() + config.linger);
+                        }
+                        buf.push(item);
+                        if buf.len() >= config.max_batch_size {
+                            flush_auto_flush_buffer(&producer, &mut buf).await;
+                            deadline = None;
+                        }
+                    }
+                    None => {
+                        // Sender side (and the `TaskQueueProducer` that owns it) has been
+                        // dropped -- flush what's left and shut down.
+                        flush_auto_flush_buffer(&producer, &mut buf).await;
+                        return;
+                    }
+                }
+            }
+            _ = sleep, if deadline.is_some() => {
+                flush_auto_flush_buffer(&producer, &mut buf).await;
+                deadline = None;
+            }
+        }
+    }
+}
+
+async fn flush_auto_flush_buffer(producer: &TaskQueueProducer, buf: &mut Vec<PendingSend>) {
+    if buf.is_empty() {
+        return;
+    }
+
+    let pending = std::mem::take(buf);
+    let (tasks, replies): (Vec<_>, Vec<_>) = pending
+        .into_iter()
+        .map(|p| ((p.task, p.delay), p.reply))
+        .unzip();
+
+    let results = producer.send_batch(tasks).await;
+    for (reply, result) in replies.into_iter().zip(results) {
+        // The caller may have given up waiting; nothing to do if so.
+        let _ = reply.send(result);
+    }
 }
 
 pub enum TaskQueueConsumer {
     Redis(RedisQueueConsumer),
     RabbitMq(rabbitmq::Consumer),
-    Omni(DynConsumer),
+    Postgres(PostgresQueueConsumer),
+    Omni(DynConsumer, Option<Arc<ResultBackend>>),
 }
 
 impl TaskQueueConsumer {
@@ -189,7 +479,8 @@ impl TaskQueueConsumer {
         match self {
             TaskQueueConsumer::Redis(q) => q.receive_all().await.trace(),
             TaskQueueConsumer::RabbitMq(q) => q.receive_all().await.trace(),
-            TaskQueueConsumer::Omni(q) => {
+            TaskQueueConsumer::Postgres(q) => q.receive_all().await.trace(),
+            TaskQueueConsumer::Omni(q, result_backend) => {
                 const MAX_MESSAGES: usize = 128;
                 // FIXME(onelson): need to figure out what deadline/duration to use here
                 q.receive_all(MAX_MESSAGES, Duration::from_secs(30))
@@ -197,35 +488,88 @@ impl TaskQueueConsumer {
                     .map_err(Into::into)
                     .trace()?
                     .into_iter()
-                    .map(TryInto::try_into)
+                    .map(|delivery| TaskQueueDelivery::from_omni(delivery, result_backend.clone()))
                     .collect()
             }
         }
     }
+
+    /// Drains the backend's dead-letter destination, returning the poisoned tasks so an
+    /// operator can inspect them and, if appropriate, replay them through
+    /// `TaskQueueProducer::send`.
+    pub async fn drain_dead_letters(&mut self) -> Result<Vec<DeadLetteredTask>> {
+        match self {
+            TaskQueueConsumer::Redis(q) => q.drain_dead_letters().await.trace(),
+            TaskQueueConsumer::RabbitMq(q) => q.drain_dead_letters().await.trace(),
+            TaskQueueConsumer::Postgres(q) => q.drain_dead_letters().await.trace(),
+            TaskQueueConsumer::Omni(..) => Err(Error::queue(
+                "dead-letter draining is not supported for the in-memory queue backend",
+            )),
+        }
+    }
 }
 
 /// Used by TaskQueueDeliveries to Ack/Nack itself
 #[derive(Debug)]
 enum Acker {
-    Redis(Arc<RedisQueueInner>),
+    /// The second field is the physical stream entry id, distinct from `TaskQueueDelivery::id`
+    /// (the stable scheduling id `send` returned) -- it's what `XACK`/`XCLAIM` need to address
+    /// this specific delivery.
+    Redis(Arc<RedisQueueInner>, String),
     RabbitMQ(lapin::message::Delivery),
+    Postgres(Arc<PostgresQueueInner>),
     Omni(Delivery),
 }
 
+/// A task pulled out of a backend's dead-letter destination by `TaskQueueConsumer::drain_dead_letters`.
+/// Unlike `TaskQueueDelivery` it carries no acker -- it has already been removed from the
+/// dead-letter destination, so the only thing left to do with it is inspect it or replay it
+/// via `TaskQueueProducer::send`.
+#[derive(Debug, Clone)]
+pub struct DeadLetteredTask {
+    pub id: String,
+    pub task: Arc<QueueTask>,
+}
+
 #[derive(Debug)]
 pub struct TaskQueueDelivery {
+    /// The same id `TaskQueueProducer::send` returned for this task. The Redis, RabbitMQ and
+    /// Postgres backends carry this id through to delivery (in the stream entry, a header, or
+    /// the row's own primary key) rather than minting a new one here, so that a caller holding
+    /// `send`'s id can use it with `cancel_scheduled` *and* `await_result` on those backends.
+    /// The in-memory backend does not carry it: its deliveries have an empty id.
     pub id: String,
     pub task: Arc<QueueTask>,
+    /// How many times this task has been delivered, including this delivery. Compared against
+    /// `max_delivery_attempts` by `nack` to decide whether to dead-letter instead of requeueing a
+    /// poisoned task.
+    pub delivery_count: u16,
+    /// Set by the consumer from `Configuration` at construction time, rather than threaded
+    /// through as a `nack` argument -- `nack` has no caller-supplied knob to keep in sync, so
+    /// every call site gets dead-lettering "for free" once the operator configures a limit.
+    max_delivery_attempts: Option<u16>,
+    /// Set by the consumer from `Configuration` at construction time, same as
+    /// `max_delivery_attempts`. Lets `ack`/`dead_letter` record this delivery's terminal outcome
+    /// so a caller of `TaskQueueProducer::await_result` can observe it.
+    result_backend: Option<Arc<ResultBackend>>,
     acker: Acker,
 }
 
 impl TaskQueueDelivery {
-    /// The `timestamp` is when this message will be delivered at
-    fn from_arc(task: Arc<QueueTask>, timestamp: Option<DateTime<Utc>>, acker: Acker) -> Self {
-        let ksuid = KsuidMs::new(timestamp, None);
+    fn from_arc(
+        id: String,
+        task: Arc<QueueTask>,
+        delivery_count: u16,
+        max_delivery_attempts: Option<u16>,
+        result_backend: Option<Arc<ResultBackend>>,
+        acker: Acker,
+    ) -> Self {
         Self {
-            id: ksuid.to_string(),
+            id,
             task,
+            delivery_count,
+            max_delivery_attempts,
+            result_backend,
             acker,
         }
     }
@@ -235,14 +579,15 @@ impl TaskQueueDelivery {
 
         let mut retry = Retry::new(should_retry, RETRY_SCHEDULE);
         let mut acker = Some(self.acker);
-        loop {
+        let result = loop {
             if let Some(result) = retry
                 .run(|| async {
                     let acker_ref = acker
                         .as_ref()
                         .expect("acker is always Some when trying to ack");
                     match acker_ref {
-                        Acker::Redis(q) => q.ack(&self.id, &self.task).await.trace(),
+                        Acker::Redis(q, entry_id) => q.ack(entry_id, &self.id).await.trace(),
+                        Acker::Postgres(q) => q.ack(&self.id).await.trace(),
                         Acker::RabbitMQ(delivery) => {
                             delivery
                                 .ack(BasicAckOptions {
@@ -266,12 +611,24 @@ impl TaskQueueDelivery {
                 })
                 .await
             {
-                return result;
+                break result;
             }
+        };
+
+        if result.is_ok() {
+            record_outcome(&self.result_backend, &self.id, TaskOutcome::Succeeded).await;
         }
+        result
     }
 
+    /// Nacks the delivery so the backend redelivers it, unless `max_delivery_attempts` was
+    /// configured and this delivery has already reached it -- in which case it is dead-lettered
+    /// instead, so a task that fails every time doesn't loop forever and starve the worker.
     pub async fn nack(self) -> Result<()> {
+        if should_dead_letter(self.delivery_count, self.max_delivery_attempts) {
+            return self.dead_letter().await;
+        }
+
         tracing::trace!("nack {}", self.id);
 
         let mut retry = Retry::new(should_retry, RETRY_SCHEDULE);
@@ -283,7 +640,8 @@ impl TaskQueueDelivery {
                         .as_ref()
                         .expect("acker is always Some when trying to ack");
                     match acker_ref {
-                        Acker::Redis(q) => q.nack(&self.id, &self.task).await.trace(),
+                        Acker::Redis(q, entry_id) => q.nack(entry_id, &self.id, &self.task).await.trace(),
+                        Acker::Postgres(q) => q.nack(&self.id).await.trace(),
                         Acker::RabbitMQ(delivery) => {
                             // See https://www.rabbitmq.com/confirms.html#consumer-nacks-requeue
 
@@ -318,11 +676,75 @@ impl TaskQueueDelivery {
             }
         }
     }
+
+    /// Routes the delivery to the backend's dead-letter destination instead of acking or
+    /// requeueing it. Called by `nack` once `delivery_count` reaches `max_delivery_attempts`;
+    /// exposed on its own so a caller can also dead-letter a task outright (e.g. after a
+    /// non-retryable error). The in-memory backend has no dead-letter destination: there this
+    /// nacks the delivery, which requeues it.
+    pub async fn dead_letter(self) -> Result<()> {
+        tracing::warn!(
+            "dead-lettering {} after {} delivery attempt(s)",
+            self.id,
+            self.delivery_count
+        );
+
+        let mut retry = Retry::new(should_retry, RETRY_SCHEDULE);
+        let mut acker = Some(self.acker);
+        let result = loop {
+            if let Some(result) = retry
+                .run(|| async {
+                    let acker_ref = acker
+                        .as_ref()
+                        .expect("acker is always Some when trying to dead-letter");
+                    match acker_ref {
+                        Acker::Redis(q, entry_id) => {
+                            q.dead_letter(entry_id, &self.id, &self.task).await.trace()
+                        }
+                        Acker::Postgres(q) => q.dead_letter(&self.id, &self.task).await.trace(),
+                        Acker::RabbitMQ(delivery) => {
+                            // The queue is declared with `x-dead-letter-exchange`, so rejecting
+                            // without requeueing hands the message straight to the DLX/DLQ.
+                            delivery
+                                .reject(BasicRejectOptions { requeue: false })
+                                .await
+                                .map_err(Into::into)
+                        }
+                        Acker::Omni(_) => match acker.take() {
+                            Some(Acker::Omni(delivery)) => {
+                                delivery
+                                    .nack()
+                                    .await
+                                    .map_err(|(e, delivery)| {
+                                        // Put the delivery back in acker before retrying, to
+                                        // satisfy the expect above.
+                                        acker = Some(Acker::Omni(delivery));
+                                        e.into()
+                                    })
+                                    .trace()
+                            }
+                            _ => unreachable!(),
+                        },
+                    }
+                })
+                .await
+            {
+                break result;
+            }
+        };
+
+        if result.is_ok() {
+            record_outcome(&self.result_backend, &self.id, TaskOutcome::Exhausted).await;
+        }
+        result
+    }
 }
 
-impl TryFrom<Delivery> for TaskQueueDelivery {
-    type Error = Error;
-    fn try_from(value: Delivery) -> Result<Self> {
+impl TaskQueueDelivery {
+    /// Builds a delivery from an in-memory/omniqueue `Delivery`. A free function taking
+    /// `result_backend` explicitly, rather than `TryFrom`, since the result backend is
+    /// per-consumer configuration that a generic trait impl has nowhere to receive.
+    fn from_omni(value: Delivery, result_backend: Option<Arc<ResultBackend>>) -> Result<Self> {
         Ok(TaskQueueDelivery {
             // FIXME(onelson): ksuid for the id?
             //   Since ack/nack is all handled internally by the omniqueue delivery, maybe it
@@ -334,6 +756,11 @@ impl TryFrom<Delivery> for TaskQueueDelivery {
                     .map_err(|_| Error::queue("Failed to decode queue task"))?
                     .ok_or_else(|| Error::queue("Unexpected empty delivery"))?,
             ),
+            // FIXME(onelson): the in-memory/omniqueue backend doesn't surface a redelivery
+            //   count, so dead-lettering via `max_delivery_attempts` is a no-op here.
+            delivery_count: 0,
+            max_delivery_attempts: None,
+            result_backend,
             acker: Acker::Omni(value),
         })
     }
@@ -341,10 +768,29 @@ impl TryFrom<Delivery> for TaskQueueDelivery {
 
 #[async_trait]
 trait TaskQueueSend: Sync + Send {
-    async fn send(&self, task: Arc<QueueTask>, delay: Option<Duration>) -> Result<()>;
+    async fn send(&self, task: Arc<QueueTask>, delay: Option<Duration>) -> Result<String>;
 }
 
 #[async_trait]
 trait TaskQueueReceive {
     async fn receive_all(&mut self) -> Result<Vec<TaskQueueDelivery>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nack_dead_letters_once_max_delivery_attempts_is_reached() {
+        assert!(!should_dead_letter(1, Some(5)));
+        assert!(!should_dead_letter(4, Some(5)));
+        assert!(should_dead_letter(5, Some(5)));
+        assert!(should_dead_letter(6, Some(5)));
+    }
+
+    #[test]
+    fn nack_never_dead_letters_without_a_configured_limit() {
+        assert!(!should_dead_letter(0, None));
+        assert!(!should_dead_letter(u16::MAX, None));
+    }
+}
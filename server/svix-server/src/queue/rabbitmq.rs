@@ -0,0 +1,331 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use axum::async_trait;
+use lapin::{
+    options::{
+        BasicConsumeOptions, BasicPublishOptions, ExchangeDeclareOptions, QueueBindOptions,
+        QueueDeclareOptions,
+    },
+    types::{AMQPValue, FieldTable},
+    BasicProperties, Channel, Connection, ConnectionProperties, ExchangeKind,
+};
+use svix_ksuid::*;
+use tokio::sync::Mutex;
+use tokio_stream::StreamExt;
+
+use super::{
+    result_backend::ResultBackend, should_retry, Acker, DeadLetteredTask, QueueTask, SendReceipt,
+    TaskQueueConsumer, TaskQueueDelivery, TaskQueueProducer, TaskQueueProducerInner, RETRY_SCHEDULE,
+};
+use crate::{
+    core::retry::run_with_retries,
+    error::{Error, Result},
+};
+
+/// Header carrying the scheduling id minted by `send`, so `receive_all` can hand back the same
+/// id as the delivery's id -- the stable id contract `TaskQueueProducer::await_result` relies on.
+const ID_HEADER: &str = "x-svix-id";
+
+fn dlx_name(queue: &str) -> String {
+    format!("{queue}-dlx")
+}
+
+fn dlq_name(queue: &str) -> String {
+    format!("{queue}-dlq")
+}
+
+/// Scheduled cancellation is approximated, not a broker primitive: RabbitMQ has no "retract a
+/// delayed message" operation, so a cancelled id is just remembered and the matching delivery is
+/// dropped (acked, not processed) the moment it's received. Only holds up within a single
+/// consumer process -- a delayed message already in flight to a different consumer instance
+/// won't see the cancellation. Good enough for the common case (an endpoint deleted before its
+/// own retry fires on the same node) without a broker-level delayed-message plugin.
+type CancelledIds = Arc<Mutex<HashSet<String>>>;
+
+#[derive(Clone)]
+pub struct Producer {
+    channel: Channel,
+    queue: String,
+    cancelled: CancelledIds,
+}
+
+#[async_trait]
+impl super::TaskQueueSend for Producer {
+    async fn send(&self, task: Arc<QueueTask>, delay: Option<Duration>) -> Result<String> {
+        let id = KsuidMs::new(None, None).to_string();
+        let payload = serde_json::to_vec(task.as_ref())
+            .map_err(|e| Error::queue(format!("failed to serialize queue task: {e}")))?;
+
+        let mut headers = FieldTable::default();
+        headers.insert(ID_HEADER.into(), AMQPValue::LongString(id.clone().into()));
+        let mut props = BasicProperties::default().with_headers(headers);
+        if let Some(delay) = delay {
+            // Per-message TTL: the broker only makes the message visible once it expires.
+            props = props.with_expiration(delay.as_millis().to_string().into());
+        }
+
+        self.channel
+            .basic_publish(
+                "",
+                &self.queue,
+                BasicPublishOptions::default(),
+                &payload,
+                props,
+            )
+            .await
+            .map_err(|e| Error::queue(format!("rabbitmq send failed: {e}")))?
+            .await
+            .map_err(|e| Error::queue(format!("rabbitmq send confirm failed: {e}")))?;
+
+        Ok(id)
+    }
+}
+
+impl Producer {
+    /// Publishes each task with a publisher confirm, retried as its own unit via
+    /// `run_with_retries`. Returns one result per input task, in the same order they were given:
+    /// a task that fails to serialize, or to publish/confirm after retries, only affects its own
+    /// slot -- it never fails any other task in the batch.
+    pub async fn send_batch(&self, tasks: Vec<(QueueTask, Option<Duration>)>) -> Vec<Result<SendReceipt>> {
+        let mut results = Vec::with_capacity(tasks.len());
+        for (task, delay) in &tasks {
+            let id = KsuidMs::new(None, None).to_string();
+            let payload = match serde_json::to_vec(task) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    results.push(Err(Error::queue(format!(
+                        "failed to serialize queue task: {e}"
+                    ))));
+                    continue;
+                }
+            };
+
+            let mut headers = FieldTable::default();
+            headers.insert(ID_HEADER.into(), AMQPValue::LongString(id.clone().into()));
+            let mut props = BasicProperties::default().with_headers(headers);
+            if let Some(delay) = delay {
+                props = props.with_expiration(delay.as_millis().to_string().into());
+            }
+
+            let result = run_with_retries(
+                || async {
+                    self.channel
+                        .basic_publish(
+                            "",
+                            &self.queue,
+                            BasicPublishOptions::default(),
+                            &payload,
+                            props.clone(),
+                        )
+                        .await
+                        .map_err(|e| Error::queue(format!("rabbitmq send_batch failed: {e}")))?
+                        .await
+                        .map_err(|e| {
+                            Error::queue(format!("rabbitmq send_batch confirm failed: {e}"))
+                        })
+                },
+                should_retry,
+                RETRY_SCHEDULE,
+            )
+            .await
+            .map(|_| SendReceipt { id: id.clone() });
+
+            results.push(result);
+        }
+        results
+    }
+
+    /// RabbitMQ has no way to retract an in-flight delayed message, so cancellation is
+    /// approximated by remembering `id` and dropping the delivery (without processing it) if it
+    /// does show up. See [`CancelledIds`].
+    pub async fn cancel_scheduled(&self, id: &str) -> Result<()> {
+        self.cancelled.lock().await.insert(id.to_string());
+        Ok(())
+    }
+}
+
+pub struct Consumer {
+    channel: Channel,
+    consumer: lapin::Consumer,
+    dlq: String,
+    cancelled: CancelledIds,
+    max_delivery_attempts: Option<u16>,
+    result_backend: Option<Arc<ResultBackend>>,
+}
+
+impl Consumer {
+    pub async fn receive_all(&mut self) -> Result<Vec<TaskQueueDelivery>> {
+        // `basic_consume` already primed `self.consumer` as a push-based stream; pull whatever's
+        // immediately available without blocking for more -- a queue backend's `receive_all` is
+        // a poll, not a long-lived subscription.
+        let mut deliveries = Vec::new();
+        while let Ok(Some(next)) =
+            tokio::time::timeout(Duration::from_millis(50), self.consumer.next()).await
+        {
+            let delivery =
+                next.map_err(|e| Error::queue(format!("rabbitmq receive failed: {e}")))?;
+
+            let id = delivery
+                .properties
+                .headers()
+                .as_ref()
+                .and_then(|headers| headers.inner().get(ID_HEADER))
+                .and_then(|value| match value {
+                    AMQPValue::LongString(s) => Some(s.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+
+            if self.cancelled.lock().await.remove(&id) {
+                delivery
+                    .ack(lapin::options::BasicAckOptions { multiple: false })
+                    .await
+                    .map_err(|e| Error::queue(format!("rabbitmq receive failed: {e}")))?;
+                continue;
+            }
+
+            let task: QueueTask = serde_json::from_slice(&delivery.data)
+                .map_err(|_| Error::queue("Failed to decode queue task"))?;
+
+            deliveries.push(TaskQueueDelivery::from_arc(
+                id,
+                Arc::new(task),
+                // RabbitMQ's `redelivered` flag is a bool, not a count; treat a redelivered
+                // message as attempt 2 so `max_delivery_attempts` eventually dead-letters a
+                // message that keeps getting nacked, even without an exact count.
+                if delivery.redelivered { 2 } else { 1 },
+                self.max_delivery_attempts,
+                self.result_backend.clone(),
+                Acker::RabbitMQ(delivery),
+            ));
+        }
+
+        Ok(deliveries)
+    }
+
+    /// Drains the dead-letter queue the main queue was declared with `x-dead-letter-exchange`
+    /// pointing at.
+    pub async fn drain_dead_letters(&mut self) -> Result<Vec<DeadLetteredTask>> {
+        let mut drained = Vec::new();
+        loop {
+            let get = self
+                .channel
+                .basic_get(&self.dlq, Default::default())
+                .await
+                .map_err(|e| Error::queue(format!("rabbitmq dead-letter drain failed: {e}")))?;
+            let Some(delivery) = get else { break };
+
+            let id = delivery
+                .properties
+                .headers()
+                .as_ref()
+                .and_then(|headers| headers.inner().get(ID_HEADER))
+                .and_then(|value| match value {
+                    AMQPValue::LongString(s) => Some(s.to_string()),
+                    _ => None,
+                })
+                .unwrap_or_default();
+            let task: QueueTask = serde_json::from_slice(&delivery.data)
+                .map_err(|_| Error::queue("Failed to decode queue task"))?;
+
+            delivery
+                .ack(lapin::options::BasicAckOptions { multiple: false })
+                .await
+                .map_err(|e| Error::queue(format!("rabbitmq dead-letter drain failed: {e}")))?;
+
+            drained.push(DeadLetteredTask {
+                id,
+                task: Arc::new(task),
+            });
+        }
+        Ok(drained)
+    }
+}
+
+pub async fn new_pair(
+    dsn: &str,
+    queue: String,
+    prefetch_size: u16,
+    max_delivery_attempts: Option<u16>,
+    result_backend: Option<Arc<ResultBackend>>,
+) -> Result<(TaskQueueProducer, TaskQueueConsumer)> {
+    let conn = Connection::connect(dsn, ConnectionProperties::default())
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq connection failed: {e}")))?;
+    let channel = conn
+        .create_channel()
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq channel creation failed: {e}")))?;
+    channel
+        .confirm_select(Default::default())
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq confirm_select failed: {e}")))?;
+
+    let dlx = dlx_name(&queue);
+    let dlq = dlq_name(&queue);
+
+    channel
+        .exchange_declare(
+            &dlx,
+            ExchangeKind::Fanout,
+            ExchangeDeclareOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq dlx declare failed: {e}")))?;
+    channel
+        .queue_declare(&dlq, QueueDeclareOptions::default(), FieldTable::default())
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq dlq declare failed: {e}")))?;
+    channel
+        .queue_bind(
+            &dlq,
+            &dlx,
+            "",
+            QueueBindOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq dlq bind failed: {e}")))?;
+
+    let mut queue_args = FieldTable::default();
+    queue_args.insert("x-dead-letter-exchange".into(), AMQPValue::LongString(dlx.clone().into()));
+    channel
+        .queue_declare(&queue, QueueDeclareOptions::default(), queue_args)
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq queue declare failed: {e}")))?;
+
+    channel
+        .basic_qos(prefetch_size, Default::default())
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq qos failed: {e}")))?;
+
+    let consumer_tag = KsuidMs::new(None, None).to_string();
+    let consumer = channel
+        .basic_consume(
+            &queue,
+            &consumer_tag,
+            BasicConsumeOptions::default(),
+            FieldTable::default(),
+        )
+        .await
+        .map_err(|e| Error::queue(format!("rabbitmq consume failed: {e}")))?;
+
+    let cancelled: CancelledIds = Arc::new(Mutex::new(HashSet::new()));
+
+    Ok((
+        TaskQueueProducer::new(TaskQueueProducerInner::RabbitMq(Producer {
+            channel: channel.clone(),
+            queue: queue.clone(),
+            cancelled: cancelled.clone(),
+        })),
+        TaskQueueConsumer::RabbitMq(Consumer {
+            channel,
+            consumer,
+            dlq,
+            cancelled,
+            max_delivery_attempts,
+            result_backend,
+        }),
+    ))
+}
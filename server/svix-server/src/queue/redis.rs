@@ -0,0 +1,486 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::async_trait;
+use chrono::Utc;
+use redis::AsyncCommands;
+use svix_ksuid::*;
+
+use super::{
+    result_backend::ResultBackend, should_retry, Acker, DeadLetteredTask, QueueTask, SendReceipt,
+    TaskQueueConsumer, TaskQueueDelivery, TaskQueueProducer, TaskQueueProducerInner, RETRY_SCHEDULE,
+};
+use crate::{
+    core::retry::run_with_retries,
+    error::{Error, Result},
+    redis::RedisPool,
+};
+
+/// How long a stream entry can sit claimed-but-unacked before [`RedisQueueConsumer::receive_all`]
+/// reclaims it for redelivery. Mirrors `postgres::LEASE_DURATION`.
+const LEASE_DURATION: Duration = Duration::from_secs(30);
+
+const MAX_MESSAGES: usize = 128;
+
+const GROUP: &str = "svix";
+
+fn stream_key(prefix: &str) -> String {
+    format!("{prefix}queue-stream")
+}
+
+fn delayed_key(prefix: &str) -> String {
+    format!("{prefix}queue-delayed")
+}
+
+fn delayed_payloads_key(prefix: &str) -> String {
+    format!("{prefix}queue-delayed-payloads")
+}
+
+fn delivery_counts_key(prefix: &str) -> String {
+    format!("{prefix}queue-delivery-counts")
+}
+
+fn dead_letter_key(prefix: &str) -> String {
+    format!("{prefix}queue-dead")
+}
+
+#[derive(Debug)]
+pub struct RedisQueueInner {
+    pool: RedisPool,
+    stream: String,
+    delayed: String,
+    delayed_payloads: String,
+    delivery_counts: String,
+    dead_letter: String,
+}
+
+impl RedisQueueInner {
+    async fn ack(&self, entry_id: &str, id: &str) -> Result<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+        redis::pipe()
+            .atomic()
+            .xack(&self.stream, GROUP, &[entry_id])
+            .hdel(&self.delivery_counts, id)
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(|e| Error::queue(format!("redis queue ack failed: {e}")))?;
+        Ok(())
+    }
+
+    /// Makes the task immediately re-deliverable: re-enqueues it at the tail of the stream under
+    /// the same entry id's fields, then acks the stale entry. The Postgres backend gets this for
+    /// free by clearing `delivered_at`; streams have no such in-place "unclaim", so a fresh entry
+    /// stands in for it. `delivery_counts` stays keyed by the stable logical `id`, not the
+    /// stream's own entry id, and is deliberately *not* cleared here -- the new entry's first
+    /// `receive_all` will `HINCRBY` the same hash key, so the count keeps accumulating across
+    /// redeliveries instead of resetting to 1 every nack.
+    async fn nack(&self, entry_id: &str, id: &str, task: &QueueTask) -> Result<()> {
+        let payload = serde_json::to_string(task)
+            .map_err(|e| Error::queue(format!("failed to serialize queue task: {e}")))?;
+
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        redis::pipe()
+            .atomic()
+            .xadd(&self.stream, "*", &[("id", id.as_str()), ("task", payload.as_str())])
+            .xack(&self.stream, GROUP, &[entry_id])
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(|e| Error::queue(format!("redis queue nack failed: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Pushes the task onto the dead-letter list and acks the poisoned entry off the main
+    /// stream, so it stops coming back on every redelivery sweep.
+    async fn dead_letter(&self, entry_id: &str, id: &str, task: &QueueTask) -> Result<()> {
+        let payload = serde_json::to_string(&DeadLetteredTask {
+            id: id.to_string(),
+            task: Arc::new(task.clone()),
+        })
+        .map_err(|e| Error::queue(format!("failed to serialize queue task: {e}")))?;
+
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        redis::pipe()
+            .atomic()
+            .lpush(&self.dead_letter, payload)
+            .xack(&self.stream, GROUP, &[entry_id])
+            .hdel(&self.delivery_counts, id)
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(|e| Error::queue(format!("redis queue dead-letter failed: {e}")))?;
+
+        Ok(())
+    }
+
+    async fn drain_dead_letters(&self) -> Result<Vec<DeadLetteredTask>> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        let mut drained = Vec::new();
+        loop {
+            let payload: Option<String> = conn
+                .rpop(&self.dead_letter, None)
+                .await
+                .map_err(|e| Error::queue(format!("redis queue dead-letter drain failed: {e}")))?;
+            let Some(payload) = payload else { break };
+            let task: DeadLetteredTask = serde_json::from_str(&payload)
+                .map_err(|_| Error::queue("Failed to decode queue task"))?;
+            drained.push(task);
+        }
+        Ok(drained)
+    }
+
+    /// Moves every delayed task whose `scheduled_for` has passed onto the live stream. Each
+    /// candidate is claimed with `ZREM` before being promoted, so two consumers racing on the
+    /// same due task can't both re-enqueue it.
+    async fn promote_due(&self) -> Result<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        let now_ms = Utc::now().timestamp_millis();
+        let due: Vec<String> = conn
+            .zrangebyscore(&self.delayed, 0, now_ms)
+            .await
+            .map_err(|e| Error::queue(format!("redis queue promote failed: {e}")))?;
+
+        for id in due {
+            let claimed: i64 = conn
+                .zrem(&self.delayed, &id)
+                .await
+                .map_err(|e| Error::queue(format!("redis queue promote failed: {e}")))?;
+            if claimed == 0 {
+                // Another consumer already claimed and promoted this one.
+                continue;
+            }
+
+            let payload: Option<String> = conn
+                .hget(&self.delayed_payloads, &id)
+                .await
+                .map_err(|e| Error::queue(format!("redis queue promote failed: {e}")))?;
+            let Some(payload) = payload else { continue };
+
+            redis::pipe()
+                .atomic()
+                .xadd(&self.stream, "*", &[("id", id.as_str()), ("task", payload.as_str())])
+                .hdel(&self.delayed_payloads, &id)
+                .query_async::<_, ()>(&mut conn)
+                .await
+                .map_err(|e| Error::queue(format!("redis queue promote failed: {e}")))?;
+        }
+
+        Ok(())
+    }
+
+    async fn cancel_scheduled(&self, id: &str) -> Result<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        redis::pipe()
+            .atomic()
+            .zrem(&self.delayed, id)
+            .hdel(&self.delayed_payloads, id)
+            .query_async::<_, ()>(&mut conn)
+            .await
+            .map_err(|e| Error::queue(format!("redis queue cancel_scheduled failed: {e}")))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct RedisQueueProducer {
+    inner: Arc<RedisQueueInner>,
+}
+
+#[async_trait]
+impl super::TaskQueueSend for RedisQueueProducer {
+    async fn send(&self, task: Arc<QueueTask>, delay: Option<Duration>) -> Result<String> {
+        let scheduled_for = Utc::now()
+            + chrono::Duration::from_std(delay.unwrap_or_default()).unwrap_or(chrono::Duration::zero());
+        let id = KsuidMs::new(Some(scheduled_for), None).to_string();
+        let payload = serde_json::to_string(task.as_ref())
+            .map_err(|e| Error::queue(format!("failed to serialize queue task: {e}")))?;
+
+        let mut conn = self
+            .inner
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        match delay {
+            None | Some(Duration::ZERO) => {
+                conn.xadd(&self.inner.stream, "*", &[("id", id.as_str()), ("task", payload.as_str())])
+                    .await
+                    .map_err(|e| Error::queue(format!("redis queue send failed: {e}")))?
+            }
+            Some(_) => {
+                redis::pipe()
+                    .atomic()
+                    .zadd(&self.inner.delayed, &id, scheduled_for.timestamp_millis())
+                    .hset(&self.inner.delayed_payloads, &id, &payload)
+                    .query_async(&mut conn)
+                    .await
+                    .map_err(|e| Error::queue(format!("redis queue send failed: {e}")))?
+            }
+        }
+
+        Ok(id)
+    }
+}
+
+impl RedisQueueProducer {
+    /// Enqueues every `(task, delay)` pair as a single pipelined round trip.
+    /// Pipelines every task as a single atomic round trip, retried as one unit via
+    /// `run_with_retries`. Returns one result per input task, in the same order they were given:
+    /// a task that fails to serialize gets its own `Err` without the pipeline (and the rest of
+    /// the batch) ever being attempted, rather than failing every other task in the batch along
+    /// with it.
+    pub async fn send_batch(&self, tasks: Vec<(QueueTask, Option<Duration>)>) -> Vec<Result<SendReceipt>> {
+        let mut results: Vec<Option<Result<SendReceipt>>> = (0..tasks.len()).map(|_| None).collect();
+        let mut entries = Vec::with_capacity(tasks.len());
+        for (i, (task, delay)) in tasks.iter().enumerate() {
+            let scheduled_for = Utc::now()
+                + chrono::Duration::from_std(delay.unwrap_or_default()).unwrap_or(chrono::Duration::zero());
+            let id = KsuidMs::new(Some(scheduled_for), None).to_string();
+            match serde_json::to_string(task) {
+                Ok(payload) => entries.push((i, id, payload, *delay, scheduled_for)),
+                Err(e) => {
+                    results[i] = Some(Err(Error::queue(format!(
+                        "failed to serialize queue task: {e}"
+                    ))))
+                }
+            }
+        }
+
+        if !entries.is_empty() {
+            let outcome = run_with_retries(
+                || async {
+                    let mut conn = self.inner.pool.get().await.map_err(|e| {
+                        Error::queue(format!("redis queue connection failed: {e}"))
+                    })?;
+
+                    let mut pipe = redis::pipe();
+                    pipe.atomic();
+                    for (_, id, payload, delay, scheduled_for) in &entries {
+                        match delay {
+                            None | Some(Duration::ZERO) => {
+                                pipe.xadd(
+                                    &self.inner.stream,
+                                    "*",
+                                    &[("id", id.as_str()), ("task", payload.as_str())],
+                                );
+                            }
+                            Some(_) => {
+                                pipe.zadd(&self.inner.delayed, id, scheduled_for.timestamp_millis());
+                                pipe.hset(&self.inner.delayed_payloads, id, payload);
+                            }
+                        }
+                    }
+
+                    pipe.query_async::<_, ()>(&mut conn)
+                        .await
+                        .map_err(|e| Error::queue(format!("redis queue send_batch failed: {e}")))
+                },
+                should_retry,
+                RETRY_SCHEDULE,
+            )
+            .await;
+
+            match outcome {
+                Ok(()) => {
+                    for (i, id, ..) in entries {
+                        results[i] = Some(Ok(SendReceipt { id }));
+                    }
+                }
+                Err(e) => {
+                    let msg = e.to_string();
+                    for (i, ..) in entries {
+                        results[i] = Some(Err(Error::queue(msg.clone())));
+                    }
+                }
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|r| r.expect("every task index is filled by either the serialize or pipeline step"))
+            .collect()
+    }
+
+    pub async fn cancel_scheduled(&self, id: &str) -> Result<()> {
+        self.inner.cancel_scheduled(id).await
+    }
+}
+
+pub struct RedisQueueConsumer {
+    inner: Arc<RedisQueueInner>,
+    consumer_name: String,
+    max_delivery_attempts: Option<u16>,
+    result_backend: Option<Arc<ResultBackend>>,
+}
+
+/// Pulls the `id`/`task` fields back out of a raw stream entry. Returns `None` for a
+/// malformed entry (should never happen -- every entry is written by `send`/`send_batch`/
+/// `promote_due`) rather than failing the whole batch over one bad row.
+fn decode_entry(entry: redis::streams::StreamId) -> Option<(String, String, String)> {
+    let mut id = None;
+    let mut task_json = None;
+    for (field, value) in entry.map {
+        let value: String = redis::from_redis_value(&value).ok()?;
+        match field.as_str() {
+            "id" => id = Some(value),
+            "task" => task_json = Some(value),
+            _ => {}
+        }
+    }
+    Some((entry.id, id?, task_json?))
+}
+
+impl RedisQueueConsumer {
+    pub async fn receive_all(&mut self) -> Result<Vec<TaskQueueDelivery>> {
+        self.inner.promote_due().await?;
+
+        let mut conn = self
+            .inner
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("redis queue connection failed: {e}")))?;
+
+        // Reclaim entries that were delivered to a consumer that crashed (or is just slow)
+        // before it acked or nacked them -- the stream equivalent of Postgres's expired-lease
+        // sweep.
+        let (_cursor, reclaimed): (String, Vec<redis::streams::StreamId>) = redis::cmd("XAUTOCLAIM")
+            .arg(&self.inner.stream)
+            .arg(GROUP)
+            .arg(&self.consumer_name)
+            .arg(LEASE_DURATION.as_millis() as u64)
+            .arg("0-0")
+            .arg("COUNT")
+            .arg(MAX_MESSAGES)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| Error::queue(format!("redis queue receive failed: {e}")))?;
+
+        let new: Vec<redis::streams::StreamId> = conn
+            .xread_options(
+                &[&self.inner.stream],
+                &[">"],
+                &redis::streams::StreamReadOptions::default()
+                    .group(GROUP, &self.consumer_name)
+                    .count(MAX_MESSAGES),
+            )
+            .await
+            .map(|reply: redis::streams::StreamReadReply| {
+                reply
+                    .keys
+                    .into_iter()
+                    .flat_map(|key| key.ids)
+                    .collect()
+            })
+            .map_err(|e| Error::queue(format!("redis queue receive failed: {e}")))?;
+
+        let mut deliveries = Vec::with_capacity(reclaimed.len() + new.len());
+        for (entry_id, id, task_json) in reclaimed.into_iter().chain(new).filter_map(decode_entry) {
+            let task: QueueTask = serde_json::from_str(&task_json)
+                .map_err(|_| Error::queue("Failed to decode queue task"))?;
+
+            let delivery_count: u16 = conn
+                .hincr(&self.inner.delivery_counts, &id, 1i64)
+                .await
+                .map_err(|e| Error::queue(format!("redis queue receive failed: {e}")))?;
+
+            deliveries.push(TaskQueueDelivery::from_arc(
+                id,
+                Arc::new(task),
+                delivery_count,
+                self.max_delivery_attempts,
+                self.result_backend.clone(),
+                Acker::Redis(self.inner.clone(), entry_id),
+            ));
+        }
+
+        Ok(deliveries)
+    }
+
+    pub async fn drain_dead_letters(&mut self) -> Result<Vec<DeadLetteredTask>> {
+        self.inner.drain_dead_letters().await
+    }
+}
+
+pub async fn new_pair(
+    pool: RedisPool,
+    prefix: Option<&str>,
+    max_delivery_attempts: Option<u16>,
+    result_backend: Option<Arc<ResultBackend>>,
+) -> (TaskQueueProducer, TaskQueueConsumer) {
+    let prefix = prefix.map(|p| format!("{p}-")).unwrap_or_default();
+    let inner = Arc::new(RedisQueueInner {
+        pool,
+        stream: stream_key(&prefix),
+        delayed: delayed_key(&prefix),
+        delayed_payloads: delayed_payloads_key(&prefix),
+        delivery_counts: delivery_counts_key(&prefix),
+        dead_letter: dead_letter_key(&prefix),
+    });
+
+    let mut conn = inner
+        .pool
+        .get()
+        .await
+        .expect("can't connect to redis queue backend");
+    // `MKSTREAM` so the group can be created against a stream that doesn't exist yet;
+    // `BUSYGROUP` (the group already exists) is the expected case on every restart after the
+    // first, so it's swallowed rather than treated as a startup failure.
+    let created: redis::RedisResult<()> = redis::cmd("XGROUP")
+        .arg("CREATE")
+        .arg(&inner.stream)
+        .arg(GROUP)
+        .arg("0")
+        .arg("MKSTREAM")
+        .query_async(&mut conn)
+        .await;
+    if let Err(e) = created {
+        if !e.to_string().contains("BUSYGROUP") {
+            panic!("can't create redis queue consumer group: {e}");
+        }
+    }
+    drop(conn);
+
+    let consumer_name = KsuidMs::new(None, None).to_string();
+
+    (
+        TaskQueueProducer::new(TaskQueueProducerInner::Redis(RedisQueueProducer {
+            inner: inner.clone(),
+        })),
+        TaskQueueConsumer::Redis(RedisQueueConsumer {
+            inner,
+            consumer_name,
+            max_delivery_attempts,
+            result_backend,
+        }),
+    )
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::{error::Error, error::Result, redis::RedisPool};
+
+/// Terminal outcome of a task, written by the worker once it's no longer in flight (after an
+/// `ack` or a dead-letter). Stored under a short TTL so `TaskQueueProducer::await_result`
+/// callers have a window to pick it up before it expires.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "status")]
+pub enum TaskOutcome {
+    Succeeded,
+    Failed { reason: String },
+    Exhausted,
+}
+
+/// How long a recorded outcome survives before expiring, giving callers of `await_result` a
+/// window to observe it after the worker writes it.
+const RESULT_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// How often `await_result` polls for the outcome to show up.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+fn result_key(id: &str) -> String {
+    format!("svix-queue-result:{id}")
+}
+
+/// Opt-in Redis-backed store of task outcomes, keyed by delivery id. Lets a synchronous caller
+/// (an API handler, a test) block on a task's outcome instead of sleeping arbitrarily.
+#[derive(Clone)]
+pub struct ResultBackend {
+    pool: RedisPool,
+}
+
+impl ResultBackend {
+    pub fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+
+    /// Records the terminal outcome for `id`. Called by the worker after `ack`-ing or
+    /// dead-lettering a delivery.
+    pub async fn set_outcome(&self, id: &str, outcome: &TaskOutcome) -> Result<()> {
+        let mut conn = self
+            .pool
+            .get()
+            .await
+            .map_err(|e| Error::queue(format!("result backend connection failed: {e}")))?;
+        let payload = serde_json::to_string(outcome)
+            .map_err(|e| Error::queue(format!("failed to serialize task outcome: {e}")))?;
+
+        conn.set_ex::<_, _, ()>(result_key(id), payload, RESULT_TTL.as_secs())
+            .await
+            .map_err(|e| Error::queue(format!("result backend write failed: {e}")))?;
+
+        Ok(())
+    }
+
+    /// Polls for `id`'s outcome, returning it as soon as it appears or erroring once `timeout`
+    /// elapses without one.
+    pub async fn await_result(&self, id: &str, timeout: Duration) -> Result<TaskOutcome> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let mut conn = self
+                .pool
+                .get()
+                .await
+                .map_err(|e| Error::queue(format!("result backend connection failed: {e}")))?;
+            let payload: Option<String> = conn
+                .get(result_key(id))
+                .await
+                .map_err(|e| Error::queue(format!("result backend read failed: {e}")))?;
+
+            if let Some(payload) = payload {
+                return serde_json::from_str(&payload)
+                    .map_err(|_| Error::queue("Failed to decode task outcome"));
+            }
+
+            if Instant::now() >= deadline {
+                return Err(Error::queue(format!(
+                    "timed out after {:?} waiting for the result of task {id}",
+                    timeout
+                )));
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}